@@ -0,0 +1,84 @@
+use chrono::{Local, NaiveDateTime};
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::schema::assignments;
+use crate::utils::generate_random_id;
+
+#[derive(Serialize, Deserialize, Queryable, AsChangeset, Insertable, Identifiable, Clone, Debug)]
+#[table_name = "assignments"]
+#[primary_key(assignment_id)]
+pub struct Assignment {
+    pub assignment_id: String,
+    pub class_id: Option<String>,
+    pub creator: Option<String>,
+    pub assignment_name: Option<String>,
+    pub instructions: Option<String>,
+    pub due_date: Option<NaiveDateTime>,
+    pub draft: bool,
+    pub created_at: NaiveDateTime,
+}
+
+impl Default for Assignment {
+    fn default() -> Self {
+        Self {
+            assignment_id: generate_random_id().to_string(),
+            class_id: None,
+            creator: None,
+            assignment_name: None,
+            instructions: None,
+            due_date: None,
+            draft: true,
+            created_at: Local::now().naive_local(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Validate)]
+#[table_name = "assignments"]
+pub struct FillableAssignments {
+    pub creator: Option<String>,
+    #[validate(length(min = 1, message = "assignment_name must not be empty"))]
+    pub assignment_name: Option<String>,
+    pub instructions: Option<String>,
+    pub due_date: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+pub struct AssignmentData {
+    pub id: String,
+    #[validate]
+    pub assignment: FillableAssignments,
+}
+
+impl Assignment {
+    pub fn draft(&self, conn: &PgConnection) -> QueryResult<Self> {
+        diesel::insert_into(assignments::table)
+            .values(self)
+            .execute(conn)?;
+
+        assignments::table
+            .find(&self.assignment_id)
+            .get_result::<Self>(conn)
+    }
+
+    pub fn get_by_id(id: &String, conn: &PgConnection) -> QueryResult<Self> {
+        assignments::table.find(id).get_result::<Self>(conn)
+    }
+
+    pub fn delete(&self, conn: &PgConnection) -> QueryResult<Self> {
+        diesel::delete(assignments::table.find(&self.assignment_id)).get_result::<Self>(conn)
+    }
+
+    /// Deletes this assignment and everything that hangs off it in one
+    /// transaction; the `ON DELETE CASCADE` foreign keys do the actual
+    /// cleanup of dependent rows.
+    pub fn delete_cascade(&self, conn: &PgConnection) -> QueryResult<Self> {
+        conn.transaction(|| {
+            diesel::delete(assignments::table.find(&self.assignment_id)).get_result::<Self>(conn)
+        })
+    }
+}
@@ -5,7 +5,6 @@ use rocket::serde::json::serde_json::json;
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
 use rocket_dyn_templates::handlebars::JsonValue;
-use tokio;
 
 use crate::assignments::models::AssignmentData;
 use crate::assignments::models::{Assignment, FillableAssignments};
@@ -15,14 +14,18 @@ use crate::comments::models::{Comment, Commenter, PrivateComment};
 use crate::db;
 use crate::db::DbConn;
 use crate::files::models::UploadedFile;
+use crate::jobs;
 use crate::links::models::Link;
+use crate::mail;
+use crate::mail::{render, AssignmentMailContext, MailTemplate};
 use crate::schema::attachments;
 use crate::submissions::models::{FillableSubmissions, Submissions};
 use crate::traits::Embedable;
 use crate::traits::{ClassUser, Manipulable};
 use crate::users::models::{Student, User, ResponseUser};
 use crate::users::routes::get_user;
-use crate::utils::{generate_random_id, update, mailer};
+use crate::utils::{generate_random_id, update};
+use crate::validation::validate_or_422;
 
 #[post("/<class_id>/assignments")]
 pub fn draft(key: ClassGuard, class_id: &str, conn: db::DbConn) -> Result<Json<JsonValue>, Status> {
@@ -34,17 +37,19 @@ pub fn draft(key: ClassGuard, class_id: &str, conn: db::DbConn) -> Result<Json<J
 }
 
 #[patch("/<class_id>/assignments", data = "<data>")]
-pub async fn update_assignment(
+pub fn update_assignment(
     key: ClassGuard,
     class_id: &str,
     data: Json<AssignmentData>,
     conn: db::DbConn,
-) -> Result<Json<JsonValue>, Status> {
+) -> Result<Json<JsonValue>, (Status, Json<JsonValue>)> {
     let data = data.into_inner();
 
+    validate_or_422(&data)?;
+
     let assignment = match Assignment::get_by_id(&data.id, &conn) {
         Ok(v) => v,
-        Err(_) => return Err(Status::NotFound),
+        Err(_) => return Err((Status::NotFound, Json(json!({"error": "assignment not found"})))),
     };
 
     let students = Student::load_in_class(&class_id.to_string(), &conn).unwrap();
@@ -56,7 +61,12 @@ pub async fn update_assignment(
         };
         match Submissions::create(new_submission, &conn) {
             Ok(s) => (),
-            Err(_) => return Err(Status::InternalServerError),
+            Err(_) => {
+                return Err((
+                    Status::InternalServerError,
+                    Json(json!({"error": "failed to create submission"})),
+                ))
+            }
         }
     }
 
@@ -74,41 +84,51 @@ pub async fn update_assignment(
         emails.push(User::find_user(&i.user_id, &conn).unwrap().email)
     }
     
-    send_mail(creator, emails, new.clone()).await;
+    enqueue_mail(creator, emails, new.clone(), &conn);
 
     Ok(Json(json!({ "new_assignment": new })))
 }
 
-async fn send_mail(user: User, emails: Vec<String>, assignment: Assignment) {
+fn enqueue_mail(user: User, emails: Vec<String>, assignment: Assignment, conn: &PgConnection) {
 
-    let mail = mailer().0;
+    let template = if assignment.draft {
+        MailTemplate::NewAssignment
+    } else {
+        MailTemplate::AssignmentUpdated
+    };
 
-    let server = mailer().1;
+    let context = AssignmentMailContext {
+        creator_name: user.fullname.clone(),
+        assignment_name: assignment
+            .assignment_name
+            .clone()
+            .unwrap_or_else(|| "Untitled assignment".to_string()),
+        instructions: assignment
+            .instructions
+            .clone()
+            .unwrap_or_else(|| "No instructions provided.".to_string()),
+        due_date: assignment
+            .due_date
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| "No due date".to_string()),
+        class_name: assignment
+            .class_id
+            .clone()
+            .unwrap_or_else(|| "your class".to_string()),
+        action_url: format!("/assignments/{}", assignment.assignment_id),
+    };
 
-    let html = format!(r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Hello from Lettre!</title>
-</head>
-<body>
-    <div style="display: block; align-items: center;">
-        <h2 style="font-family: Arial, Helvetica, sans-serif;">New Assignment from {}: {}</h2>
-        <br>
-        <h4 style="font-family: Arial, Helvetica, sans-serif;">{}</h4>
-    </div>
-</body>
-</html>"#, &user.fullname, &assignment.assignment_name.unwrap(), &assignment.instructions.unwrap());
+    let subject = template.subject();
 
-    let mail = mail.clone().server(server)
-                            .subject("New Assignment");
+    let html = match render(template, &mail::default_locale(), &context) {
+        Ok(html) => html,
+        Err(_) => return,
+    };
 
     for email in emails {
-        let send = mail.clone().to(email.as_str()).message(html.as_str(), "H").clone().send();
-        let job = tokio::task::spawn(async move {
-            send.await.unwrap()
-        });
+        if let Err(e) = jobs::enqueue_assignment_mail(&email, subject, &html, conn) {
+            eprintln!("failed to enqueue assignment mail for {}: {}", email, e);
+        }
     }
 }
 
@@ -136,24 +156,17 @@ pub fn delete_assignment(
         }
     }
 
-    assignment.delete(&conn).unwrap();
-
-    let att = match Attachment::load_by_assignment_id(&assignment.assignment_id, &conn) {
-        Ok(v) => v,
-        Err(_) => return Err(Status::NotFound),
-    };
-
-    att.into_iter().for_each(|i| {
-        i.delete(&conn).unwrap();
-    });
-
-    Ok(Status::Ok)
+    match assignment.delete_cascade(&conn) {
+        Ok(_) => Ok(Status::Ok),
+        Err(_) => Err(Status::InternalServerError),
+    }
 }
 
 #[derive(Serialize)]
 struct AssignmentResponse {
     attachment: Attachment,
     file: Option<UploadedFile>,
+    file_url: Option<String>,
     link: Option<Link>,
 }
 
@@ -161,12 +174,20 @@ fn get_attachments(vec: Vec<Attachment>, conn: &PgConnection) -> Vec<AssignmentR
     let mut res = Vec::<AssignmentResponse>::new();
 
     for thing in vec {
+        let file = match &thing.file_id {
+            Some(id) => Some(UploadedFile::receive(id, conn).unwrap()),
+            None => None,
+        };
+
+        let file_url = match &file {
+            Some(f) => f.download_url().ok(),
+            None => None,
+        };
+
         let resp = AssignmentResponse {
             attachment: thing.clone(),
-            file: match &thing.file_id {
-                Some(id) => Some(UploadedFile::receive(id, conn).unwrap()),
-                None => None,
-            },
+            file,
+            file_url,
             link: match &thing.link_id {
                 Some(id) => Some(Link::receive(id, conn).unwrap()),
                 None => None,
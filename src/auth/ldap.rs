@@ -0,0 +1,122 @@
+use ldap3::{LdapConn, Scope, SearchEntry};
+
+/// Present only when `LDAP_SERVER_URL` is set, so deployments that don't
+/// run a directory get local bcrypt auth unchanged.
+pub struct LdapConfig {
+    pub server_url: String,
+    pub bind_dn_template: String,
+    pub search_base: String,
+    pub role_attribute: String,
+}
+
+pub fn config_from_env() -> Option<LdapConfig> {
+    Some(LdapConfig {
+        server_url: std::env::var("LDAP_SERVER_URL").ok()?,
+        bind_dn_template: std::env::var("LDAP_BIND_DN_TEMPLATE").ok()?,
+        search_base: std::env::var("LDAP_SEARCH_BASE").ok()?,
+        role_attribute: std::env::var("LDAP_ROLE_ATTRIBUTE")
+            .unwrap_or_else(|_| "employeeType".to_string()),
+    })
+}
+
+/// Directory attributes synced onto the local `User` row after a
+/// successful bind. `role` is the raw value of `config.role_attribute`
+/// (e.g. "teacher"/"admin"), left unmapped here since `Role` lives in
+/// `users::models`; the caller falls back to `Role::Student` when it's
+/// missing or doesn't match a known role.
+pub struct DirectoryProfile {
+    pub fullname: String,
+    pub email: String,
+    pub role: Option<String>,
+}
+
+/// Escapes a value for safe inclusion in an LDAP search filter, per
+/// RFC 4515: `\`, `*`, `(`, `)`, and NUL become `\XX` hex escapes.
+fn escape_filter_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\5c"),
+            '*' => escaped.push_str("\\2a"),
+            '(' => escaped.push_str("\\28"),
+            ')' => escaped.push_str("\\29"),
+            '\0' => escaped.push_str("\\00"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Escapes a value for safe inclusion in an RFC 4514 distinguished-name
+/// component. DN syntax treats `, + " \ < > ;` and a leading `#` or
+/// leading/trailing space as structural, which `escape_filter_value`
+/// (RFC 4515 search-filter escaping) doesn't cover — using it to build
+/// `bind_dn` would let a username inject extra DN components.
+fn escape_dn_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let chars: Vec<char> = value.chars().collect();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        match ch {
+            '\\' | ',' | '+' | '"' | '<' | '>' | ';' | '=' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\0' => escaped.push_str("\\00"),
+            '#' | ' ' if i == 0 => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            ' ' if i == chars.len() - 1 => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+/// Returns `None` on any bind or lookup failure so the caller can fall
+/// back to local auth.
+pub fn authenticate(config: &LdapConfig, username: &str, password: &str) -> Option<DirectoryProfile> {
+    // An empty password binds "unauthenticated" per RFC 4513 §5.1.2 and many
+    // directory servers report that as a successful bind, so reject it
+    // before it ever reaches `simple_bind`.
+    if password.is_empty() {
+        return None;
+    }
+
+    let bind_dn = config
+        .bind_dn_template
+        .replace("{username}", &escape_dn_value(username));
+
+    let mut ldap = LdapConn::new(&config.server_url).ok()?;
+    ldap.simple_bind(&bind_dn, password).ok()?.success().ok()?;
+
+    let (results, _) = ldap
+        .search(
+            &config.search_base,
+            Scope::Subtree,
+            &format!("(uid={})", escape_filter_value(username)),
+            vec!["cn", "mail", &config.role_attribute],
+        )
+        .ok()?
+        .success()
+        .ok()?;
+
+    let entry = results.into_iter().next().map(SearchEntry::construct)?;
+
+    let fullname = entry.attrs.get("cn")?.get(0)?.clone();
+    let email = entry.attrs.get("mail")?.get(0)?.clone();
+    let role = entry
+        .attrs
+        .get(&config.role_attribute)
+        .and_then(|values| values.get(0))
+        .cloned();
+
+    let _ = ldap.unbind();
+
+    Some(DirectoryProfile { fullname, email, role })
+}
@@ -0,0 +1,189 @@
+use chrono::{Duration, Local};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::serde::json::serde_json::json;
+use rocket::serde::json::{Json, Value as JsonValue};
+use serde::{Deserialize, Serialize};
+
+use crate::db::DbConn;
+use crate::users::models::User;
+
+const ACCESS_TOKEN_TTL_MINUTES: i64 = 30;
+const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+fn secret() -> String {
+    std::env::var("JWT_SECRET").expect("JWT_SECRET must be set")
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Claims {
+    pub sub: String,
+    pub role: String,
+    pub typ: TokenType,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    Missing,
+    Invalid,
+    Expired,
+}
+
+fn issue_token(user_id: &str, role: &str, typ: TokenType, ttl: Duration) -> String {
+    let now = Local::now();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        role: role.to_string(),
+        typ,
+        iat: now.timestamp(),
+        exp: (now + ttl).timestamp(),
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret().as_bytes()),
+    )
+    .expect("failed to sign jwt")
+}
+
+pub fn issue_access_token(user_id: &str, role: &str) -> String {
+    issue_token(
+        user_id,
+        role,
+        TokenType::Access,
+        Duration::minutes(ACCESS_TOKEN_TTL_MINUTES),
+    )
+}
+
+pub fn issue_refresh_token(user_id: &str, role: &str) -> String {
+    issue_token(
+        user_id,
+        role,
+        TokenType::Refresh,
+        Duration::days(REFRESH_TOKEN_TTL_DAYS),
+    )
+}
+
+#[derive(Serialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+pub fn issue_token_pair(user_id: &str, role: &str) -> TokenPair {
+    TokenPair {
+        access_token: issue_access_token(user_id, role),
+        refresh_token: issue_refresh_token(user_id, role),
+    }
+}
+
+fn decode_claims(token: &str) -> Result<Claims, AuthError> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret().as_bytes()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+    .map_err(|e| match e.kind() {
+        jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::Expired,
+        _ => AuthError::Invalid,
+    })
+}
+
+fn bearer_token(req: &Request<'_>) -> Option<&str> {
+    req.headers()
+        .get_one("Authorization")?
+        .strip_prefix("Bearer ")
+}
+
+pub struct ApiKey(pub String, pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKey {
+    type Error = AuthError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match bearer_token(req) {
+            None => Outcome::Failure((Status::Unauthorized, AuthError::Missing)),
+            Some(token) => match decode_claims(token) {
+                Ok(claims) if claims.typ != TokenType::Access => {
+                    Outcome::Failure((Status::Unauthorized, AuthError::Invalid))
+                }
+                Ok(claims) => Outcome::Success(ApiKey(claims.sub, claims.role)),
+                Err(e) => Outcome::Failure((Status::Unauthorized, e)),
+            },
+        }
+    }
+}
+
+pub struct ClassGuard(pub String, pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClassGuard {
+    type Error = AuthError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match bearer_token(req) {
+            None => Outcome::Failure((Status::Unauthorized, AuthError::Missing)),
+            Some(token) => match decode_claims(token) {
+                Ok(claims) if claims.typ != TokenType::Access => {
+                    Outcome::Failure((Status::Unauthorized, AuthError::Invalid))
+                }
+                Ok(claims) => Outcome::Success(ClassGuard(claims.sub, claims.role)),
+                Err(e) => Outcome::Failure((Status::Unauthorized, e)),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest<'a> {
+    pub user_id: &'a str,
+    pub password: &'a str,
+}
+
+#[post("/auth/login", data = "<data>")]
+pub fn login(data: Json<LoginRequest>, conn: DbConn) -> Result<Json<JsonValue>, Status> {
+    let user = User::get_by_key(&data.user_id.to_string(), data.password.to_string(), &conn)
+        .ok_or(Status::Unauthorized)?;
+
+    let tokens = issue_token_pair(&user.user_id, &user.status);
+
+    Ok(Json(json!({
+        "access_token": tokens.access_token,
+        "refresh_token": tokens.refresh_token,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct RefreshRequest<'a> {
+    pub refresh_token: &'a str,
+}
+
+#[post("/auth/refresh", data = "<data>")]
+pub fn refresh(data: Json<RefreshRequest>) -> Result<Json<JsonValue>, Status> {
+    let claims = decode_claims(data.refresh_token).map_err(|_| Status::Unauthorized)?;
+
+    if claims.typ != TokenType::Refresh {
+        return Err(Status::Unauthorized);
+    }
+
+    let access_token = issue_access_token(&claims.sub, &claims.role);
+
+    Ok(Json(json!({ "access_token": access_token })))
+}
+
+pub fn mount(rocket: rocket::Rocket<rocket::Build>) -> rocket::Rocket<rocket::Build> {
+    rocket.mount("/api", routes![login, refresh])
+}
@@ -0,0 +1,83 @@
+use chrono::{Local, NaiveDateTime};
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::files;
+use crate::storage::{self, StorageBackend, StorageKind};
+use crate::utils::generate_random_id;
+
+#[derive(Serialize, Deserialize, Queryable, AsChangeset, Insertable, Clone)]
+#[table_name = "files"]
+pub struct UploadedFile {
+    pub file_id: String,
+    pub file_name: String,
+    pub storage_backend: String,
+    pub object_key: String,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct FillableUploadedFile<'a> {
+    pub file_name: &'a str,
+    pub bytes: &'a [u8],
+}
+
+impl UploadedFile {
+    pub fn receive(id: &str, conn: &PgConnection) -> QueryResult<Self> {
+        files::table.find(id).get_result::<Self>(conn)
+    }
+
+    /// Writes `bytes` through the configured `StorageBackend` and persists
+    /// the resulting backend kind and object key as a new file row.
+    pub fn store(
+        new_data: FillableUploadedFile,
+        conn: &PgConnection,
+    ) -> Result<Self, storage::StorageError> {
+        let backend = storage::from_env()?;
+        let file_id = generate_random_id().to_string();
+        let key = backend.store(&file_id, new_data.bytes)?;
+
+        let uploaded = Self {
+            file_id,
+            file_name: new_data.file_name.to_string(),
+            storage_backend: backend.kind().as_str().to_string(),
+            object_key: key,
+            created_at: Local::now().naive_local(),
+        };
+
+        diesel::insert_into(files::table)
+            .values(&uploaded)
+            .execute(conn)
+            .map_err(|_| storage::StorageError::NotConfigured)?;
+
+        Ok(uploaded)
+    }
+
+    /// Resolves a download URL for this file through its own backend, not
+    /// necessarily the one currently configured via the environment — this
+    /// lets older, locally-stored files keep working after a migration to S3.
+    pub fn download_url(&self) -> Result<String, storage::StorageError> {
+        let backend: Box<dyn StorageBackend> = match StorageKind::from_str(&self.storage_backend) {
+            StorageKind::S3 => Box::new(storage::S3::new(
+                &std::env::var("STORAGE_S3_BUCKET").map_err(|_| storage::StorageError::NotConfigured)?,
+                &std::env::var("STORAGE_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                std::env::var("STORAGE_S3_ENDPOINT").ok().as_deref(),
+                &std::env::var("STORAGE_S3_ACCESS_KEY").map_err(|_| storage::StorageError::NotConfigured)?,
+                &std::env::var("STORAGE_S3_SECRET_KEY").map_err(|_| storage::StorageError::NotConfigured)?,
+                3600,
+            )?),
+            StorageKind::Local => Box::new(storage::LocalDisk::new(
+                std::env::var("STORAGE_LOCAL_ROOT").unwrap_or_else(|_| "uploads".to_string()),
+                std::env::var("STORAGE_LOCAL_PUBLIC_PREFIX")
+                    .unwrap_or_else(|_| "/static/uploads".to_string()),
+            )),
+        };
+
+        backend.url_for(&self.object_key)
+    }
+
+    pub fn delete(&self, conn: &PgConnection) -> QueryResult<Self> {
+        diesel::delete(files::table.find(&self.file_id)).get_result::<Self>(conn)
+    }
+}
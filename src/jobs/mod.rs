@@ -0,0 +1,163 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use diesel;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use rocket::serde::json::serde_json;
+use rocket::serde::json::serde_json::Value as JsonValue;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Pool;
+use crate::schema::jobs;
+use crate::utils::{generate_random_id, mailer};
+
+const MAX_ATTEMPTS: i32 = 5;
+const POLL_INTERVAL_SECS: u64 = 5;
+
+#[derive(Serialize, Deserialize, Queryable, AsChangeset, Insertable, Identifiable, Clone)]
+#[table_name = "jobs"]
+#[primary_key(job_id)]
+pub struct Job {
+    pub job_id: String,
+    pub kind: String,
+    pub payload: JsonValue,
+    pub attempts: i32,
+    pub next_run_at: NaiveDateTime,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AssignmentMailPayload {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+}
+
+impl Job {
+    fn enqueue(kind: &str, payload: JsonValue, conn: &PgConnection) -> QueryResult<Self> {
+        let job = Self {
+            job_id: generate_random_id().to_string(),
+            kind: kind.to_string(),
+            payload,
+            attempts: 0,
+            next_run_at: Local::now().naive_local(),
+            status: "pending".to_string(),
+            created_at: Local::now().naive_local(),
+        };
+
+        diesel::insert_into(jobs::table).values(&job).execute(conn)?;
+
+        jobs::table.find(&job.job_id).get_result::<Self>(conn)
+    }
+}
+
+pub fn enqueue_assignment_mail(to: &str, subject: &str, html: &str, conn: &PgConnection) -> QueryResult<Job> {
+    let payload = serde_json::to_value(AssignmentMailPayload {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        html: html.to_string(),
+    })
+    .expect("job payload must serialize");
+
+    Job::enqueue("assignment_mail", payload, conn)
+}
+
+async fn process(job: &Job) -> Result<(), String> {
+    match job.kind.as_str() {
+        "assignment_mail" => {
+            let payload: AssignmentMailPayload =
+                serde_json::from_value(job.payload.clone()).map_err(|e| e.to_string())?;
+
+            let (mail, server) = mailer();
+
+            mail.clone()
+                .server(server)
+                .subject(payload.subject.as_str())
+                .to(payload.to.as_str())
+                .message(payload.html.as_str(), "H")
+                .clone()
+                .send()
+                .await
+                .map_err(|e| e.to_string())
+        }
+        other => Err(format!("unknown job kind: {}", other)),
+    }
+}
+
+fn reschedule(job: &Job, conn: &PgConnection) -> QueryResult<()> {
+    let attempts = job.attempts + 1;
+
+    if attempts >= MAX_ATTEMPTS {
+        diesel::update(jobs::table.find(&job.job_id))
+            .set((jobs::status.eq("failed"), jobs::attempts.eq(attempts)))
+            .execute(conn)?;
+    } else {
+        let backoff = Duration::seconds(2i64.pow(attempts as u32));
+        diesel::update(jobs::table.find(&job.job_id))
+            .set((
+                jobs::status.eq("pending"),
+                jobs::attempts.eq(attempts),
+                jobs::next_run_at.eq(Local::now().naive_local() + backoff),
+            ))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+fn claim_due_jobs(conn: &PgConnection) -> QueryResult<Vec<Job>> {
+    conn.transaction(|| {
+        let due = jobs::table
+            .filter(jobs::status.eq("pending"))
+            .filter(jobs::next_run_at.le(Local::now().naive_local()))
+            .for_update()
+            .skip_locked()
+            .load::<Job>(conn)?;
+
+        let ids: Vec<String> = due.iter().map(|job| job.job_id.clone()).collect();
+        diesel::update(jobs::table.filter(jobs::job_id.eq_any(ids)))
+            .set(jobs::status.eq("processing"))
+            .execute(conn)?;
+
+        Ok(due)
+    })
+}
+
+async fn run_due_jobs(conn: &PgConnection) -> QueryResult<()> {
+    let due = claim_due_jobs(conn)?;
+
+    for job in due {
+        match process(&job).await {
+            Ok(_) => {
+                diesel::update(jobs::table.find(&job.job_id))
+                    .set(jobs::status.eq("done"))
+                    .execute(conn)?;
+            }
+            Err(_) => reschedule(&job, conn)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Call once at launch, with the same pool the request guards use.
+pub fn spawn_worker(pool: Pool) {
+    rocket::tokio::task::spawn(async move {
+        let mut interval = rocket::tokio::time::interval(StdDuration::from_secs(POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let conn = match pool.get() {
+                Ok(conn) => conn,
+                Err(_) => continue,
+            };
+
+            if let Err(e) = run_due_jobs(&conn).await {
+                eprintln!("job worker failed to run due jobs: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,77 @@
+use rocket_dyn_templates::handlebars::Handlebars;
+use rust_embed::RustEmbed;
+use serde::Serialize;
+
+#[derive(RustEmbed)]
+#[folder = "templates/mail"]
+struct MailAssets;
+
+pub enum MailTemplate {
+    NewAssignment,
+    AssignmentUpdated,
+}
+
+impl MailTemplate {
+    fn file_name(&self) -> &'static str {
+        match self {
+            MailTemplate::NewAssignment => "new_assignment.hbs",
+            MailTemplate::AssignmentUpdated => "assignment_updated.hbs",
+        }
+    }
+
+    fn locale_file_name(&self, locale: &str) -> String {
+        match self.file_name().rsplit_once('.') {
+            Some((stem, ext)) => format!("{}.{}.{}", stem, locale, ext),
+            None => format!("{}.{}", self.file_name(), locale),
+        }
+    }
+
+    pub fn subject(&self) -> &'static str {
+        match self {
+            MailTemplate::NewAssignment => "New Assignment",
+            MailTemplate::AssignmentUpdated => "Assignment Updated",
+        }
+    }
+}
+
+/// Reads `MAIL_DEFAULT_LOCALE`, defaulting to `"en"`, for callers that don't
+/// otherwise have a per-recipient locale to pass to `render`.
+pub fn default_locale() -> String {
+    std::env::var("MAIL_DEFAULT_LOCALE").unwrap_or_else(|_| "en".to_string())
+}
+
+/// Optional fields on the underlying `Assignment` should be filled with a
+/// placeholder by the caller rather than left out, since handlebars has no
+/// knowledge of Rust's `Option` and would otherwise render nothing at all.
+#[derive(Serialize)]
+pub struct AssignmentMailContext {
+    pub creator_name: String,
+    pub assignment_name: String,
+    pub instructions: String,
+    pub due_date: String,
+    pub class_name: String,
+    pub action_url: String,
+}
+
+/// Renders `template` using the `locale`-specific file (`<name>.<locale>.hbs`)
+/// when one is embedded, falling back to the default `<name>.hbs` otherwise.
+pub fn render(template: MailTemplate, locale: &str, ctx: &AssignmentMailContext) -> Result<String, String> {
+    let locale_name = template.locale_file_name(locale);
+
+    let (key, asset) = match MailAssets::get(&locale_name) {
+        Some(asset) => (locale_name, asset),
+        None => {
+            let default_name = template.file_name().to_string();
+            let asset = MailAssets::get(&default_name)
+                .ok_or_else(|| format!("missing mail template {}", default_name))?;
+            (default_name, asset)
+        }
+    };
+
+    let source = std::str::from_utf8(asset.data.as_ref()).map_err(|e| e.to_string())?;
+
+    let mut hb = Handlebars::new();
+    hb.register_template_string(&key, source).map_err(|e| e.to_string())?;
+
+    hb.render(&key, ctx).map_err(|e| e.to_string())
+}
@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate rocket;
+
+mod assignments;
+mod attachments;
+mod auth;
+mod comments;
+mod db;
+mod files;
+mod jobs;
+mod links;
+mod mail;
+mod schema;
+mod storage;
+mod submissions;
+mod traits;
+mod users;
+mod utils;
+mod validation;
+
+use rocket::{Build, Rocket};
+
+#[launch]
+fn rocket() -> Rocket<Build> {
+    let pool = db::init_pool();
+
+    jobs::spawn_worker(pool.clone());
+
+    users::routes::mount(auth::mount(rocket::build().manage(pool)))
+}
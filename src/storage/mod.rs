@@ -0,0 +1,217 @@
+use std::fs;
+use std::fmt;
+use std::path::PathBuf;
+
+use s3::bucket::Bucket;
+use s3::creds::Credentials;
+use s3::region::Region;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    S3(String),
+    NotConfigured,
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "storage io error: {}", e),
+            StorageError::S3(e) => write!(f, "storage s3 error: {}", e),
+            StorageError::NotConfigured => write!(f, "storage backend is not configured"),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Local,
+    S3,
+}
+
+impl StorageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageKind::Local => "local",
+            StorageKind::S3 => "s3",
+        }
+    }
+
+    pub fn from_str(kind: &str) -> Self {
+        match kind {
+            "s3" => StorageKind::S3,
+            _ => StorageKind::Local,
+        }
+    }
+}
+
+/// `key` is an opaque, backend-specific identifier (a relative path for
+/// `LocalDisk`, an object key for `S3`) stored on `UploadedFile` instead of
+/// an absolute path.
+pub trait StorageBackend: Send + Sync {
+    fn kind(&self) -> StorageKind;
+
+    fn store(&self, key: &str, bytes: &[u8]) -> StorageResult<String>;
+
+    fn retrieve(&self, key: &str) -> StorageResult<Vec<u8>>;
+
+    fn delete(&self, key: &str) -> StorageResult<()>;
+
+    fn url_for(&self, key: &str) -> StorageResult<String>;
+}
+
+pub struct LocalDisk {
+    root: PathBuf,
+    public_prefix: String,
+}
+
+impl LocalDisk {
+    pub fn new(root: impl Into<PathBuf>, public_prefix: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            public_prefix: public_prefix.into(),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl StorageBackend for LocalDisk {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Local
+    }
+
+    fn store(&self, key: &str, bytes: &[u8]) -> StorageResult<String> {
+        let path = self.path_for(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, bytes)?;
+        Ok(key.to_string())
+    }
+
+    fn retrieve(&self, key: &str) -> StorageResult<Vec<u8>> {
+        Ok(fs::read(self.path_for(key))?)
+    }
+
+    fn delete(&self, key: &str) -> StorageResult<()> {
+        fs::remove_file(self.path_for(key))?;
+        Ok(())
+    }
+
+    fn url_for(&self, key: &str) -> StorageResult<String> {
+        Ok(format!("{}/{}", self.public_prefix.trim_end_matches('/'), key))
+    }
+}
+
+pub struct S3 {
+    bucket: Bucket,
+    presign_expiry_secs: u32,
+}
+
+impl S3 {
+    pub fn new(
+        bucket_name: &str,
+        region: &str,
+        endpoint: Option<&str>,
+        access_key: &str,
+        secret_key: &str,
+        presign_expiry_secs: u32,
+    ) -> StorageResult<Self> {
+        let region = match endpoint {
+            Some(endpoint) => Region::Custom {
+                region: region.to_string(),
+                endpoint: endpoint.to_string(),
+            },
+            None => region.parse().map_err(|e: s3::error::S3Error| StorageError::S3(e.to_string()))?,
+        };
+
+        let credentials = Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+
+        let bucket = Bucket::new(bucket_name, region, credentials)
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+
+        Ok(Self {
+            bucket,
+            presign_expiry_secs,
+        })
+    }
+}
+
+impl StorageBackend for S3 {
+    fn kind(&self) -> StorageKind {
+        StorageKind::S3
+    }
+
+    fn store(&self, key: &str, bytes: &[u8]) -> StorageResult<String> {
+        self.bucket
+            .put_object_blocking(key, bytes)
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+        Ok(key.to_string())
+    }
+
+    fn retrieve(&self, key: &str) -> StorageResult<Vec<u8>> {
+        let (data, _) = self
+            .bucket
+            .get_object_blocking(key)
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+        Ok(data)
+    }
+
+    fn delete(&self, key: &str) -> StorageResult<()> {
+        self.bucket
+            .delete_object_blocking(key)
+            .map_err(|e| StorageError::S3(e.to_string()))?;
+        Ok(())
+    }
+
+    fn url_for(&self, key: &str) -> StorageResult<String> {
+        self.bucket
+            .presign_get(key, self.presign_expiry_secs, None)
+            .map_err(|e| StorageError::S3(e.to_string()))
+    }
+}
+
+/// `STORAGE_BACKEND` selects the implementation (`local`, the default, or
+/// `s3`). The S3 variant reads `STORAGE_S3_BUCKET`, `STORAGE_S3_REGION`,
+/// `STORAGE_S3_ENDPOINT` (optional, for S3-compatible providers),
+/// `STORAGE_S3_ACCESS_KEY` and `STORAGE_S3_SECRET_KEY`.
+pub fn from_env() -> StorageResult<Box<dyn StorageBackend>> {
+    match std::env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string()).as_str() {
+        "s3" => {
+            let bucket = std::env::var("STORAGE_S3_BUCKET").map_err(|_| StorageError::NotConfigured)?;
+            let region = std::env::var("STORAGE_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+            let endpoint = std::env::var("STORAGE_S3_ENDPOINT").ok();
+            let access_key = std::env::var("STORAGE_S3_ACCESS_KEY").map_err(|_| StorageError::NotConfigured)?;
+            let secret_key = std::env::var("STORAGE_S3_SECRET_KEY").map_err(|_| StorageError::NotConfigured)?;
+
+            let backend = S3::new(
+                &bucket,
+                &region,
+                endpoint.as_deref(),
+                &access_key,
+                &secret_key,
+                3600,
+            )?;
+
+            Ok(Box::new(backend))
+        }
+        _ => {
+            let root = std::env::var("STORAGE_LOCAL_ROOT").unwrap_or_else(|_| "uploads".to_string());
+            let public_prefix = std::env::var("STORAGE_LOCAL_PUBLIC_PREFIX")
+                .unwrap_or_else(|_| "/static/uploads".to_string());
+            Ok(Box::new(LocalDisk::new(root, public_prefix)))
+        }
+    }
+}
@@ -7,7 +7,9 @@ use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use rocket::fs::TempFile;
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
 
+use crate::auth::ldap;
 use crate::schema::admins;
 use crate::schema::students;
 use crate::schema::teachers;
@@ -15,6 +17,16 @@ use crate::schema::users;
 use crate::traits::{Manipulable, ClassUser};
 use crate::utils::{generate_random_id, NaiveDateForm};
 
+fn validate_birth_date_in_past(birth_date: &NaiveDateForm) -> Result<(), ValidationError> {
+    if birth_date.0 >= Local::now().naive_local().date() {
+        let mut err = ValidationError::new("birth_date_in_past");
+        err.message = Some("birth_date must be in the past".into());
+        return Err(err);
+    }
+
+    Ok(())
+}
+
 pub enum Role {
     Student,
     Teacher,
@@ -57,13 +69,18 @@ pub struct User {
     pub created_at: NaiveDateTime
 }
 
-#[derive(FromForm)]
+#[derive(FromForm, Validate)]
 pub struct InsertableUser<'a> {
+    #[validate(length(min = 1, message = "user_id must not be empty"))]
     pub user_id: String,
+    #[validate(length(min = 1, message = "fullname must not be empty"))]
     pub fullname: String,
+    #[validate(email(message = "email must be a valid email address"))]
     pub email: String,
+    #[validate(length(min = 8, message = "password must be at least 8 characters"))]
     pub password: String,
     pub birth_place: String,
+    #[validate(custom = "validate_birth_date_in_past")]
     pub birth_date: NaiveDateForm,
     pub bio: String,
     pub status: String,
@@ -71,24 +88,68 @@ pub struct InsertableUser<'a> {
     pub file_name: Option<String>,
 }
 
-#[derive(FromForm)]
+#[derive(Debug)]
+pub enum CreateUserError {
+    Validation(ValidationErrors),
+    Db(diesel::result::Error),
+}
+
+impl<'a> InsertableUser<'a> {
+    /// Validates the submitted fields and builds the `User` row for
+    /// `Manipulable::create`.
+    pub fn into_user(self) -> Result<User, ValidationErrors> {
+        self.validate()?;
+
+        Ok(User {
+            user_id: self.user_id,
+            fullname: self.fullname,
+            profile_photo: self.file_name.unwrap_or_default(),
+            email: self.email,
+            password: self.password,
+            birth_place: self.birth_place,
+            birth_date: self.birth_date.0,
+            bio: self.bio,
+            status: self.status,
+            created_at: Local::now().naive_local(),
+        })
+    }
+
+    /// Validates and inserts in one step; the create route must call this
+    /// rather than `Manipulable::create` directly.
+    pub fn create(self, conn: &PgConnection) -> Result<User, CreateUserError> {
+        let user = self.into_user().map_err(CreateUserError::Validation)?;
+        Manipulable::create(user, conn).map_err(CreateUserError::Db)
+    }
+}
+
+#[derive(FromForm, Validate)]
 pub struct UpdatableUser<'a> {
+    #[validate(length(min = 1, message = "fullname must not be empty"))]
     pub fullname: String,
+    #[validate(email(message = "email must be a valid email address"))]
     pub email: String,
     pub birth_place: String,
+    #[validate(custom = "validate_birth_date_in_past")]
     pub birth_date: NaiveDateForm,
     pub bio: String,
     pub image: Option<TempFile<'a>>,
     pub file_name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Validate)]
 pub struct PasswordChange<'a> {
     pub user_id: &'a str,
     pub password: &'a str,
+    #[validate(length(min = 8, message = "new_password must be at least 8 characters"))]
     pub new_password: &'a str,
 }
 
+#[derive(Debug)]
+pub enum UpdatePasswordError {
+    Validation(ValidationErrors),
+    Mismatch,
+}
+
 #[derive(
 Serialize, Deserialize, Queryable, AsChangeset, Insertable, Associations, Identifiable, Debug,
 )]
@@ -132,6 +193,12 @@ impl User {
     }
 
     pub fn get_by_key(key_: &String, password_: String, connection: &PgConnection) -> Option<Self> {
+        if let Some(config) = ldap::config_from_env() {
+            if let Some(profile) = ldap::authenticate(&config, key_, &password_) {
+                return Self::provision_from_directory(key_, profile, connection).ok();
+            }
+        }
+
         let res = users::table
             .filter(users::user_id.eq(key_))
             .get_result::<Self>(connection);
@@ -149,6 +216,60 @@ impl User {
         }
     }
 
+    /// Creates or refreshes the local `User` row for a directory-authenticated
+    /// login, syncing `fullname`/`email` from the directory. The local
+    /// password column is never read for these accounts, so a random
+    /// placeholder hash is used when a row has to be created. First-time
+    /// provisioning maps `profile.role` (the directory's role attribute) to
+    /// `status` via `Role::from_str`, falling back to `Role::Student` when
+    /// it's missing or unrecognized.
+    fn provision_from_directory(
+        key_: &String,
+        profile: ldap::DirectoryProfile,
+        connection: &PgConnection,
+    ) -> QueryResult<Self> {
+        match Self::find_user(key_, connection) {
+            Ok(_) => {
+                diesel::update(users::table.filter(users::user_id.eq(key_)))
+                    .set((
+                        users::fullname.eq(&profile.fullname),
+                        users::email.eq(&profile.email),
+                    ))
+                    .execute(connection)?;
+
+                users::table.find(key_).get_result::<Self>(connection)
+            }
+            Err(_) => {
+                let placeholder_password = hash(generate_random_id().to_string(), DEFAULT_COST).unwrap();
+
+                let role = profile
+                    .role
+                    .as_deref()
+                    .and_then(|r| Role::from_str(r).ok())
+                    .unwrap_or(Role::Student);
+
+                let new_user = Self {
+                    user_id: key_.clone(),
+                    fullname: profile.fullname,
+                    profile_photo: String::new(),
+                    email: profile.email,
+                    password: placeholder_password,
+                    birth_place: String::new(),
+                    birth_date: Local::now().naive_local().date(),
+                    bio: String::new(),
+                    status: role.to_string(),
+                    created_at: Local::now().naive_local(),
+                };
+
+                diesel::insert_into(users::table)
+                    .values(&new_user)
+                    .execute(connection)?;
+
+                users::table.find(&new_user.user_id).get_result::<Self>(connection)
+            }
+        }
+    }
+
     pub fn get_role(key_: &String, connection: &PgConnection) -> Result<Role, String> {
         let res = users::table
             .filter(users::user_id.eq(key_))
@@ -171,7 +292,9 @@ impl User {
         }
     }
 
-    pub fn update_password(&self, data: PasswordChange, conn: &PgConnection) -> Result<(), ()> {
+    pub fn update_password(&self, data: PasswordChange, conn: &PgConnection) -> Result<(), UpdatePasswordError> {
+        data.validate().map_err(UpdatePasswordError::Validation)?;
+
         let new_hashed = hash(&data.new_password, DEFAULT_COST).unwrap();
 
         match verify(&data.password, &self.password) {
@@ -183,11 +306,11 @@ impl User {
                     Ok(())
                 }
                 else {
-                    Err(())
+                    Err(UpdatePasswordError::Mismatch)
                 }
             }
             Err(_) => {
-                return Err(())
+                return Err(UpdatePasswordError::Mismatch)
             }
         }
     }
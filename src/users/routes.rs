@@ -0,0 +1,94 @@
+use diesel::pg::PgConnection;
+use rocket::form::Form;
+use rocket::http::Status;
+use rocket::serde::json::serde_json::json;
+use rocket::serde::json::{Json, Value as JsonValue};
+use validator::Validate;
+
+use crate::auth::ApiKey;
+use crate::db::DbConn;
+use crate::traits::Manipulable;
+use crate::users::models::{
+    CreateUserError, InsertableUser, PasswordChange, UpdatableUser, UpdatePasswordError, User,
+};
+use crate::validation::validation_errors_to_422;
+
+pub fn get_user(user_id: &String, conn: &PgConnection) -> diesel::QueryResult<User> {
+    User::find_user(user_id, conn)
+}
+
+#[post("/users", data = "<data>")]
+pub fn create(
+    data: Form<InsertableUser<'_>>,
+    conn: DbConn,
+) -> Result<Json<JsonValue>, (Status, Json<JsonValue>)> {
+    let user = data.into_inner().create(&conn).map_err(|e| match e {
+        CreateUserError::Validation(errors) => validation_errors_to_422(errors),
+        CreateUserError::Db(_) => (
+            Status::InternalServerError,
+            Json(json!({"error": "failed to create user"})),
+        ),
+    })?;
+
+    Ok(Json(json!({ "user_id": user.user_id })))
+}
+
+#[patch("/users/<user_id>", data = "<data>")]
+pub fn update(
+    key: ApiKey,
+    user_id: &str,
+    data: Form<UpdatableUser<'_>>,
+    conn: DbConn,
+) -> Result<Json<JsonValue>, (Status, Json<JsonValue>)> {
+    if key.0 != user_id {
+        return Err((Status::Forbidden, Json(json!({"error": "cannot update another user"}))));
+    }
+
+    let data = data.into_inner();
+
+    data.validate().map_err(validation_errors_to_422)?;
+
+    let existing = get_user(&user_id.to_string(), &conn)
+        .map_err(|_| (Status::NotFound, Json(json!({"error": "user not found"}))))?;
+
+    let updated = User {
+        fullname: data.fullname,
+        profile_photo: data.file_name.unwrap_or_else(|| existing.profile_photo.clone()),
+        email: data.email,
+        birth_place: data.birth_place,
+        birth_date: data.birth_date.0,
+        bio: data.bio,
+        ..existing.clone()
+    };
+
+    let new = existing
+        .update(updated, &conn)
+        .map_err(|_| (Status::InternalServerError, Json(json!({"error": "failed to update user"}))))?;
+
+    Ok(Json(json!({ "user": new })))
+}
+
+#[post("/users/password", data = "<data>")]
+pub fn change_password(
+    key: ApiKey,
+    data: Json<PasswordChange<'_>>,
+    conn: DbConn,
+) -> Result<Status, (Status, Json<JsonValue>)> {
+    let user = get_user(&key.0, &conn)
+        .map_err(|_| (Status::NotFound, Json(json!({"error": "user not found"}))))?;
+
+    user.update_password(data.into_inner(), &conn)
+        .map_err(|e| match e {
+            UpdatePasswordError::Validation(errors) => validation_errors_to_422(errors),
+            UpdatePasswordError::Mismatch => (
+                Status::Unauthorized,
+                Json(json!({"error": "password does not match"})),
+            ),
+        })?;
+
+    Ok(Status::Ok)
+}
+
+pub fn mount(rocket: rocket::Rocket<rocket::Build>) -> rocket::Rocket<rocket::Build> {
+    rocket.mount("/api", routes![create, update, change_password])
+}
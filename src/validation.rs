@@ -0,0 +1,30 @@
+use rocket::http::Status;
+use rocket::serde::json::serde_json::json;
+use rocket::serde::json::{Json, Value as JsonValue};
+use validator::{Validate, ValidationErrors};
+
+fn errors_to_json(errors: ValidationErrors) -> JsonValue {
+    let fields: Vec<JsonValue> = errors
+        .field_errors()
+        .iter()
+        .flat_map(|(field, errs)| {
+            errs.iter().map(move |e| {
+                json!({
+                    "field": field,
+                    "message": e.message.clone().unwrap_or_else(|| "invalid value".into()),
+                })
+            })
+        })
+        .collect();
+
+    json!({ "errors": fields })
+}
+
+pub fn validate_or_422<T: Validate>(data: &T) -> Result<(), (Status, Json<JsonValue>)> {
+    data.validate()
+        .map_err(|e| (Status::UnprocessableEntity, Json(errors_to_json(e))))
+}
+
+pub fn validation_errors_to_422(errors: ValidationErrors) -> (Status, Json<JsonValue>) {
+    (Status::UnprocessableEntity, Json(errors_to_json(errors)))
+}